@@ -1,8 +1,7 @@
 //! Error types
 
-use crate::{chain, prost};
+use crate::{chain, prost, rpc};
 use abscissa::Error;
-use signatory;
 use std::{
     any::Any,
     error::Error as StdError,
@@ -10,6 +9,7 @@ use std::{
     io,
 };
 use tendermint::amino_types::validate::ValidationError;
+use tendermint::{block, chain as tm_chain};
 
 /// Error type
 #[derive(Debug)]
@@ -30,7 +30,7 @@ impl KmsError {
 }
 
 /// Kinds of errors
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Fail)]
+#[derive(Clone, Eq, PartialEq, Debug, Fail)]
 pub enum KmsErrorKind {
     /// Access denied
     #[fail(display = "access denied")]
@@ -86,12 +86,34 @@ pub enum KmsErrorKind {
     VerificationError,
 
     /// Signature invalid
-    #[fail(display = "attempted double sign")]
-    DoubleSign,
-
-    ///Request a Signature above max height
-    #[fail(display = "requested signature above stop height")]
-    ExceedMaxHeight,
+    #[fail(
+        display = "attempted double sign for chain '{}' at height {} round {}",
+        chain_id, height, round
+    )]
+    DoubleSign {
+        /// Chain ID the conflicting votes/proposals were signed for
+        chain_id: tm_chain::Id,
+        /// Height at which the conflicting votes/proposals were signed
+        height: block::Height,
+        /// Round at which the conflicting votes/proposals were signed
+        round: i64,
+        /// Block ID already recorded in this node's chain state, if any
+        existing_block_id: Option<block::Id>,
+        /// Block ID of the message that was rejected as a double sign
+        attempted_block_id: Option<block::Id>,
+    },
+
+    /// Request a Signature above max height
+    #[fail(
+        display = "requested signature at height {} exceeds stop height {}",
+        requested, stop
+    )]
+    ExceedMaxHeight {
+        /// Height that was requested to be signed
+        requested: block::Height,
+        /// Configured height at which the KMS stops signing
+        stop: block::Height,
+    },
 }
 
 impl Display for KmsError {
@@ -130,17 +152,16 @@ impl From<serde_json::error::Error> for KmsError {
     }
 }
 
-impl From<signatory::Error> for KmsError {
-    fn from(other: signatory::Error) -> Self {
-        let kind = match other.kind() {
-            signatory::ErrorKind::Io => KmsErrorKind::IoError,
-            signatory::ErrorKind::KeyInvalid => KmsErrorKind::InvalidKey,
-            signatory::ErrorKind::ParseError => KmsErrorKind::ParseError,
-            signatory::ErrorKind::ProviderError => KmsErrorKind::SigningError,
-            signatory::ErrorKind::SignatureInvalid => KmsErrorKind::VerificationError,
-        };
+// `k256`/`ed25519-dalek` errors are opaque, so both collapse to `CryptoError`.
+impl From<k256::ecdsa::Error> for KmsError {
+    fn from(other: k256::ecdsa::Error) -> Self {
+        err!(KmsErrorKind::CryptoError, other).into()
+    }
+}
 
-        Error::new(kind, Some(other.description().to_owned())).into()
+impl From<ed25519_dalek::SignatureError> for KmsError {
+    fn from(other: ed25519_dalek::SignatureError) -> Self {
+        err!(KmsErrorKind::CryptoError, other).into()
     }
 }
 
@@ -161,14 +182,44 @@ impl From<tendermint::Error> for KmsError {
     }
 }
 
+impl From<rpc::ProtocolError> for KmsError {
+    fn from(other: rpc::ProtocolError) -> Self {
+        err!(KmsErrorKind::ProtocolError, other).into()
+    }
+}
+
 impl From<ValidationError> for KmsError {
     fn from(other: ValidationError) -> Self {
         err!(KmsErrorKind::InvalidMessageError, other).into()
     }
 }
 
+// The fields of `chain::state::StateError`'s variants are moved one-for-one into the matching `KmsErrorKind` variants.
 impl From<chain::state::StateError> for KmsError {
     fn from(other: chain::state::StateError) -> Self {
-        err!(KmsErrorKind::DoubleSign, other).into()
+        match other {
+            chain::state::StateError::DoubleSign {
+                chain_id,
+                height,
+                round,
+                existing_block_id,
+                attempted_block_id,
+            } => err!(
+                KmsErrorKind::DoubleSign {
+                    chain_id,
+                    height,
+                    round,
+                    existing_block_id,
+                    attempted_block_id,
+                },
+                "refused to sign a conflicting vote/proposal"
+            )
+            .into(),
+            chain::state::StateError::ExceedMaxHeight { requested, stop } => err!(
+                KmsErrorKind::ExceedMaxHeight { requested, stop },
+                "refused to sign above the configured stop height"
+            )
+            .into(),
+        }
     }
 }
@@ -3,21 +3,313 @@
 // TODO: docs for everything
 #![allow(missing_docs)]
 
-use crate::{
-    prost::encoding::{decode_varint, encoded_len_varint},
-    prost::Message,
-};
+use crate::{prost, prost::encoding::decode_varint, prost::Message};
 
-use bytes::IntoBuf;
 use lazy_static::lazy_static;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest, Sha256};
+use std::fmt::{self, Display};
 use std::io::Cursor;
 use std::io::{self, Read};
-use std::io::{Error, ErrorKind};
+use tendermint::account;
 use tendermint::amino_types::*;
 
-/// Maximum size of an RPC message
-pub const MAX_MSG_LEN: usize = 1024;
+/// Default maximum size of an RPC message, used unless the connection owner
+/// configures a different `max_msg_len`.
+pub const DEFAULT_MAX_MSG_LEN: usize = 1024;
+
+/// Wire framing spoken on the privval socket.
+///
+/// Tendermint <= 0.33 frames requests with Amino: a length prefix followed
+/// by a 4-byte registered-type prefix (see `compute_prefix` below).
+/// Tendermint >= 0.34 dropped Amino in favor of a length-delimited Protobuf
+/// `Message` oneof (`tendermint/privval/types.proto`), tagging each request
+/// by field number instead. The KMS picks which one to speak per chain, so
+/// it can serve older and newer validators side by side.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Protocol {
+    /// Legacy Amino framing (Tendermint <= 0.33)
+    Amino,
+    /// Length-delimited Protobuf framing (Tendermint >= 0.34)
+    Protobuf,
+}
+
+impl Default for Protocol {
+    fn default() -> Self {
+        Protocol::Amino
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Protocol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        match s.as_str() {
+            "amino" => Ok(Protocol::Amino),
+            "protobuf" => Ok(Protocol::Protobuf),
+            _ => Err(de::Error::custom(format!(
+                "expected \"amino\" or \"protobuf\", got {:?}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Protocol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Protocol::Amino => "amino",
+            Protocol::Protobuf => "protobuf",
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Field numbers of the `sum` oneof in Tendermint's `privval.Message`,
+/// i.e. which request/response type a length-delimited Protobuf frame holds.
+mod privval_field {
+    pub const PUBKEY_REQUEST: u64 = 1;
+    pub const SIGN_VOTE_REQUEST: u64 = 3;
+    pub const SIGN_PROPOSAL_REQUEST: u64 = 5;
+    pub const PING_REQUEST: u64 = 7;
+}
+
+/// Standalone `prost`-derived message types for `tendermint/privval/types.proto`, decoded with no Amino envelope.
+mod proto {
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct BlockId {
+        #[prost(bytes, tag = "1")]
+        pub hash: Vec<u8>,
+        #[prost(message, tag = "2")]
+        pub part_set_header: Option<PartSetHeader>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct PartSetHeader {
+        #[prost(uint32, tag = "1")]
+        pub total: u32,
+        #[prost(bytes, tag = "2")]
+        pub hash: Vec<u8>,
+    }
+
+    /// `google.protobuf.Timestamp`, as embedded in `Vote`/`Proposal`.
+    #[derive(Clone, PartialEq, Message)]
+    pub struct Timestamp {
+        #[prost(int64, tag = "1")]
+        pub seconds: i64,
+        #[prost(int32, tag = "2")]
+        pub nanos: i32,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct Vote {
+        #[prost(int32, tag = "1")]
+        pub vote_type: i32,
+        #[prost(int64, tag = "2")]
+        pub height: i64,
+        #[prost(int32, tag = "3")]
+        pub round: i32,
+        #[prost(message, tag = "4")]
+        pub block_id: Option<BlockId>,
+        #[prost(message, tag = "5")]
+        pub timestamp: Option<Timestamp>,
+        #[prost(bytes, tag = "6")]
+        pub validator_address: Vec<u8>,
+        #[prost(int32, tag = "7")]
+        pub validator_index: i32,
+        #[prost(bytes, tag = "8")]
+        pub signature: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct Proposal {
+        #[prost(int32, tag = "1")]
+        pub proposal_type: i32,
+        #[prost(int64, tag = "2")]
+        pub height: i64,
+        #[prost(int32, tag = "3")]
+        pub round: i32,
+        #[prost(int32, tag = "4")]
+        pub pol_round: i32,
+        #[prost(message, tag = "5")]
+        pub block_id: Option<BlockId>,
+        #[prost(message, tag = "6")]
+        pub timestamp: Option<Timestamp>,
+        #[prost(bytes, tag = "7")]
+        pub signature: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct SignVoteRequest {
+        #[prost(message, tag = "1")]
+        pub vote: Option<Vote>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct SignProposalRequest {
+        #[prost(message, tag = "1")]
+        pub proposal: Option<Proposal>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct PubKeyRequest {
+        #[prost(string, tag = "1")]
+        pub chain_id: String,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct PingRequest {}
+}
+
+impl From<proto::PartSetHeader> for PartSetHeader {
+    fn from(other: proto::PartSetHeader) -> Self {
+        PartSetHeader {
+            total: other.total,
+            hash: other.hash,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::BlockId> for BlockId {
+    fn from(other: proto::BlockId) -> Self {
+        BlockId {
+            hash: other.hash,
+            part_set_header: other.part_set_header.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::Timestamp> for TimeMsg {
+    fn from(other: proto::Timestamp) -> Self {
+        TimeMsg {
+            seconds: other.seconds,
+            nanos: other.nanos,
+        }
+    }
+}
+
+impl From<proto::Vote> for Vote {
+    fn from(other: proto::Vote) -> Self {
+        Vote {
+            vote_type: other.vote_type,
+            height: other.height,
+            round: other.round,
+            block_id: other.block_id.map(Into::into),
+            timestamp: other.timestamp.map(Into::into),
+            validator_address: other.validator_address,
+            validator_index: other.validator_index,
+            signature: other.signature,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::Proposal> for Proposal {
+    fn from(other: proto::Proposal) -> Self {
+        Proposal {
+            proposal_type: other.proposal_type,
+            height: other.height,
+            round: other.round,
+            pol_round: other.pol_round,
+            block_id: other.block_id.map(Into::into),
+            timestamp: other.timestamp.map(Into::into),
+            signature: other.signature,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::SignVoteRequest> for SignVoteRequest {
+    fn from(other: proto::SignVoteRequest) -> Self {
+        SignVoteRequest {
+            vote: other.vote.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::SignProposalRequest> for SignProposalRequest {
+    fn from(other: proto::SignProposalRequest) -> Self {
+        SignProposalRequest {
+            proposal: other.proposal.map(Into::into),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::PubKeyRequest> for PubKeyRequest {
+    fn from(other: proto::PubKeyRequest) -> Self {
+        PubKeyRequest {
+            chain_id: other.chain_id,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<proto::PingRequest> for PingRequest {
+    fn from(_other: proto::PingRequest) -> Self {
+        PingRequest::default()
+    }
+}
+
+/// Errors that can occur while framing/decoding an RPC message off the wire
+#[derive(Debug)]
+pub enum ProtocolError {
+    /// The peer closed the connection (or the stream ended) before a full
+    /// message could be read
+    UnexpectedEof,
+
+    /// The varint length prefix didn't decode to a sane value
+    MalformedLength,
+
+    /// The declared message length exceeded the configured `max_msg_len`
+    MessageTooLarge { len: u64, max_msg_len: usize },
+
+    /// The amino prefix didn't match any registered request type, or the
+    /// Protobuf `privval.Message` carried an unknown field number or a
+    /// non-length-delimited wire type
+    UnknownMessageType,
+
+    /// Decoding the message body (Amino or Protobuf) failed
+    Decode(prost::DecodeError),
+
+    /// The underlying reader returned an I/O error
+    Io(io::Error),
+}
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::UnexpectedEof => write!(f, "connection closed before a full message was received"),
+            ProtocolError::MalformedLength => write!(f, "malformed varint length prefix"),
+            ProtocolError::MessageTooLarge { len, max_msg_len } => write!(
+                f,
+                "RPC message too large: {} bytes exceeds max_msg_len of {} bytes",
+                len, max_msg_len
+            ),
+            ProtocolError::UnknownMessageType => write!(f, "received unknown RPC message type"),
+            ProtocolError::Decode(e) => write!(f, "failed to decode RPC message: {}", e),
+            ProtocolError::Io(e) => write!(f, "I/O error reading RPC message: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<prost::DecodeError> for ProtocolError {
+    fn from(other: prost::DecodeError) -> Self {
+        ProtocolError::Decode(other)
+    }
+}
 
 /// Requests to the KMS
 pub enum Request {
@@ -71,28 +363,47 @@ lazy_static! {
 }
 
 impl Request {
-    /// Read a request from the given readable
-    pub fn read<R: Read>(r: &mut R) -> io::Result<Self> {
-        // this buffer contains the overall length and the amino prefix (for the registered types)
-        let mut buf = vec![0; MAX_MSG_LEN];
-        let bytes_read = r.read(&mut buf)?;
-        if bytes_read < 4 {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Did not read enough bytes to continue.",
-            ));
-        }
-
-        let buff: &mut Cursor<Vec<u8>> = &mut buf.into_buf();
-        let len = decode_varint(buff).unwrap();
-        if len > MAX_MSG_LEN as u64 {
-            return Err(Error::new(ErrorKind::InvalidData, "RPC message too large."));
+    /// Read a request from the given reader, framed according to `protocol`.
+    pub fn read<R: Read>(
+        r: &mut R,
+        max_msg_len: usize,
+        protocol: Protocol,
+    ) -> Result<Self, ProtocolError> {
+        match protocol {
+            Protocol::Amino => Self::read_amino(r, max_msg_len),
+            Protocol::Protobuf => Self::read_protobuf(r, max_msg_len),
         }
+    }
+
+    /// Read a length-delimited, amino-prefixed request from the given reader.
+    ///
+    /// The varint length prefix is read one byte at a time (its own length
+    /// isn't known up front), validated against `max_msg_len`, and then
+    /// exactly that many bytes are pulled off the stream with `read_exact`
+    /// before anything is decoded. Unlike a single best-effort `read` into a
+    /// fixed buffer, this can't silently truncate a message that arrives
+    /// across several TCP segments, nor misframe whatever follows it.
+    fn read_amino<R: Read>(r: &mut R, max_msg_len: usize) -> Result<Self, ProtocolError> {
+        let len_bytes = read_varint_len_bytes(r)?;
+        let len = decode_varint(&mut Cursor::new(&len_bytes)).map_err(|_| ProtocolError::MalformedLength)?;
+        if len > max_msg_len as u64 {
+            return Err(ProtocolError::MessageTooLarge { len, max_msg_len });
+        }
+
         let mut amino_pre = vec![0; 4];
-        buff.read_exact(&mut amino_pre)?;
-        buff.set_position(0);
-        let total_len = encoded_len_varint(len).checked_add(len as usize).unwrap();
-        let rem = buff.get_ref()[..total_len].to_vec();
+        let body_len = (len as usize)
+            .checked_sub(amino_pre.len())
+            .ok_or(ProtocolError::MalformedLength)?;
+        read_exact(r, &mut amino_pre)?;
+
+        let mut body = vec![0; body_len];
+        read_exact(r, &mut body)?;
+
+        let mut rem = Vec::with_capacity(len_bytes.len() + amino_pre.len() + body.len());
+        rem.extend_from_slice(&len_bytes);
+        rem.extend_from_slice(&amino_pre);
+        rem.extend_from_slice(&body);
+
         match amino_pre {
             ref vt if *vt == *VOTE_PREFIX => Ok(Request::SignVote(SignVoteRequest::decode(&rem)?)),
             ref pr if *pr == *PROPOSAL_PREFIX => {
@@ -102,14 +413,87 @@ impl Request {
                 Ok(Request::ShowPublicKey(PubKeyRequest::decode(&rem)?))
             }
             ref ping if *ping == *PING_PREFIX => Ok(Request::ReplyPing(PingRequest::decode(&rem)?)),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Received unknown RPC message.",
+            _ => Err(ProtocolError::UnknownMessageType),
+        }
+    }
+
+    /// Read a length-delimited Protobuf `privval.Message` and decode its oneof field with the standalone `proto` types.
+    fn read_protobuf<R: Read>(r: &mut R, max_msg_len: usize) -> Result<Self, ProtocolError> {
+        let len_bytes = read_varint_len_bytes(r)?;
+        let len = decode_varint(&mut Cursor::new(&len_bytes)).map_err(|_| ProtocolError::MalformedLength)?;
+        if len > max_msg_len as u64 {
+            return Err(ProtocolError::MessageTooLarge { len, max_msg_len });
+        }
+
+        let mut msg = vec![0; len as usize];
+        read_exact(r, &mut msg)?;
+
+        let mut buf = Cursor::new(msg);
+        let key = decode_varint(&mut buf).map_err(|_| ProtocolError::MalformedLength)?;
+        let field_number = key >> 3;
+        let wire_type = key & 0x7;
+        // Every field of the `privval.Message` oneof is length-delimited (wire type 2).
+        if wire_type != 2 {
+            return Err(ProtocolError::UnknownMessageType);
+        }
+        let field_len = decode_varint(&mut buf).map_err(|_| ProtocolError::MalformedLength)?;
+
+        let start = buf.position() as usize;
+        let end = start
+            .checked_add(field_len as usize)
+            .ok_or(ProtocolError::MalformedLength)?;
+        let field = buf
+            .get_ref()
+            .get(start..end)
+            .ok_or(ProtocolError::MalformedLength)?;
+
+        match field_number {
+            privval_field::SIGN_VOTE_REQUEST => Ok(Request::SignVote(
+                proto::SignVoteRequest::decode(field)?.into(),
+            )),
+            privval_field::SIGN_PROPOSAL_REQUEST => Ok(Request::SignProposal(
+                proto::SignProposalRequest::decode(field)?.into(),
+            )),
+            privval_field::PUBKEY_REQUEST => Ok(Request::ShowPublicKey(
+                proto::PubKeyRequest::decode(field)?.into(),
             )),
+            privval_field::PING_REQUEST => Ok(Request::ReplyPing(
+                proto::PingRequest::decode(field)?.into(),
+            )),
+            _ => Err(ProtocolError::UnknownMessageType),
         }
     }
 }
 
+/// Read a varint length prefix off `r` one byte at a time, shared by the Amino and Protobuf framing paths.
+fn read_varint_len_bytes<R: Read>(r: &mut R) -> Result<Vec<u8>, ProtocolError> {
+    let mut len_bytes = Vec::with_capacity(10);
+    loop {
+        if len_bytes.len() >= 10 {
+            return Err(ProtocolError::MalformedLength);
+        }
+        let mut byte = [0u8; 1];
+        read_exact(r, &mut byte)?;
+        let has_more = byte[0] & 0x80 != 0;
+        len_bytes.push(byte[0]);
+        if !has_more {
+            break;
+        }
+    }
+    Ok(len_bytes)
+}
+
+/// Read exactly `buf.len()` bytes, translating a short read at EOF into the
+/// distinct `ProtocolError::UnexpectedEof` rather than a generic I/O error,
+/// so callers can tell a cleanly closed socket apart from a malicious peer.
+fn read_exact<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<(), ProtocolError> {
+    match r.read_exact(buf) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Err(ProtocolError::UnexpectedEof),
+        Err(e) => Err(ProtocolError::Io(e)),
+    }
+}
+
 impl TendermintRequest for SignVoteRequest {
     fn build_response(self) -> Response {
         Response::SignedVote(SignedVoteResponse {
@@ -127,3 +511,275 @@ impl TendermintRequest for SignProposalRequest {
         })
     }
 }
+
+/// Derive the validator's on-chain account ID from the Ed25519 consensus
+/// key carried in a `PubKeyResponse`.
+///
+/// `ShowPublicKey` requests only return the raw key bytes; this lets a
+/// caller holding that `Response::PublicKey` compute the address without
+/// having to guess which curve produced it.
+pub fn pub_key_account_id(
+    response: &PubKeyResponse,
+) -> Result<account::Id, ed25519_dalek::SignatureError> {
+    let pub_key = ed25519_dalek::PublicKey::from_bytes(&response.pub_key_ed25519)?;
+    Ok(account::Id::from_ed25519(&pub_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `Read` that drips out at most `chunk_size` bytes per call, so tests
+    /// can exercise the partial-read handling in `read_amino`/`read_protobuf`
+    /// the way a real socket that fills in several small TCP segments would.
+    struct ChunkedReader {
+        data: Vec<u8>,
+        pos: usize,
+        chunk_size: usize,
+    }
+
+    impl ChunkedReader {
+        fn new(data: Vec<u8>, chunk_size: usize) -> Self {
+            ChunkedReader {
+                data,
+                pos: 0,
+                chunk_size,
+            }
+        }
+    }
+
+    impl Read for ChunkedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let remaining = self.data.len() - self.pos;
+            if remaining == 0 {
+                return Ok(0);
+            }
+            let n = remaining.min(self.chunk_size).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    fn varint_bytes(value: u64) -> Vec<u8> {
+        let mut buf = bytes::BytesMut::new();
+        prost::encoding::encode_varint(value, &mut buf);
+        buf.to_vec()
+    }
+
+    fn amino_ping_frame() -> Vec<u8> {
+        let mut frame = varint_bytes(PING_PREFIX.len() as u64);
+        frame.extend_from_slice(&PING_PREFIX);
+        frame
+    }
+
+    #[test]
+    fn read_amino_handles_partial_reads() {
+        let frame = amino_ping_frame();
+        let mut r = ChunkedReader::new(frame, 1);
+        match Request::read_amino(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Ok(Request::ReplyPing(_)) => {}
+            Ok(_) => panic!("expected ReplyPing"),
+            Err(e) => panic!("expected ReplyPing, got error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn read_amino_rejects_oversized_frame() {
+        let len_bytes = varint_bytes(1000);
+        let mut r = Cursor::new(len_bytes);
+        match Request::read_amino(&mut r, 8) {
+            Err(ProtocolError::MessageTooLarge { len, max_msg_len }) => {
+                assert_eq!(len, 1000);
+                assert_eq!(max_msg_len, 8);
+            }
+            other => panic!("expected MessageTooLarge, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_amino_reports_premature_eof() {
+        // Declares a 4-byte message but the stream ends right after the
+        // length prefix.
+        let len_bytes = varint_bytes(4);
+        let mut r = Cursor::new(len_bytes);
+        match Request::read_amino(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Err(ProtocolError::UnexpectedEof) => {}
+            other => panic!("expected UnexpectedEof, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_amino_rejects_runaway_length_prefix() {
+        // 10 bytes all carrying the continuation bit, with no terminator and
+        // no 11th byte available either.
+        let data = vec![0x80; 10];
+        let mut r = Cursor::new(data);
+        match Request::read_amino(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Err(ProtocolError::MalformedLength) => {}
+            other => panic!("expected MalformedLength, got {:?}", other.err()),
+        }
+    }
+
+    /// Build a length-delimited `privval.Message` frame wrapping `body` as
+    /// field `field_number` of the oneof (wire type 2, length-delimited).
+    fn protobuf_field_frame(field_number: u64, body: &[u8]) -> Vec<u8> {
+        let tag = (field_number << 3) | 2;
+        let mut inner = varint_bytes(tag);
+        inner.extend_from_slice(&varint_bytes(body.len() as u64));
+        inner.extend_from_slice(body);
+
+        let mut frame = varint_bytes(inner.len() as u64);
+        frame.extend_from_slice(&inner);
+        frame
+    }
+
+    #[test]
+    fn read_protobuf_handles_partial_reads() {
+        let frame = protobuf_field_frame(privval_field::PING_REQUEST, &[]);
+        let mut r = ChunkedReader::new(frame, 1);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Ok(Request::ReplyPing(_)) => {}
+            Ok(_) => panic!("expected ReplyPing"),
+            Err(e) => panic!("expected ReplyPing, got error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn read_protobuf_decodes_non_empty_sign_vote_request() {
+        let vote = proto::Vote {
+            vote_type: 1,
+            height: 100,
+            round: 1,
+            timestamp: Some(proto::Timestamp {
+                seconds: 1_600_000_000,
+                nanos: 42,
+            }),
+            validator_address: vec![1, 2, 3, 4],
+            validator_index: 7,
+            signature: vec![5, 6, 7, 8],
+            ..Default::default()
+        };
+        let mut body = bytes::BytesMut::new();
+        proto::SignVoteRequest {
+            vote: Some(vote.clone()),
+        }
+        .encode(&mut body)
+        .unwrap();
+
+        let frame = protobuf_field_frame(privval_field::SIGN_VOTE_REQUEST, &body);
+        let mut r = Cursor::new(frame);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Ok(Request::SignVote(req)) => {
+                let decoded = req.vote.expect("vote");
+                assert_eq!(decoded.vote_type, vote.vote_type);
+                assert_eq!(decoded.height, vote.height);
+                assert_eq!(decoded.round, vote.round);
+                let timestamp = decoded.timestamp.expect("timestamp");
+                assert_eq!(timestamp.seconds, 1_600_000_000);
+                assert_eq!(timestamp.nanos, 42);
+                assert_eq!(decoded.validator_address, vote.validator_address);
+                assert_eq!(decoded.validator_index, vote.validator_index);
+                assert_eq!(decoded.signature, vote.signature);
+            }
+            other => panic!("expected SignVote, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_protobuf_decodes_non_empty_sign_proposal_request() {
+        let proposal = proto::Proposal {
+            proposal_type: 2,
+            height: 200,
+            round: 2,
+            pol_round: 1,
+            timestamp: Some(proto::Timestamp {
+                seconds: 1_600_000_001,
+                nanos: 99,
+            }),
+            signature: vec![9, 9, 9],
+            ..Default::default()
+        };
+        let mut body = bytes::BytesMut::new();
+        proto::SignProposalRequest {
+            proposal: Some(proposal.clone()),
+        }
+        .encode(&mut body)
+        .unwrap();
+
+        let frame = protobuf_field_frame(privval_field::SIGN_PROPOSAL_REQUEST, &body);
+        let mut r = Cursor::new(frame);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Ok(Request::SignProposal(req)) => {
+                let decoded = req.proposal.expect("proposal");
+                assert_eq!(decoded.proposal_type, proposal.proposal_type);
+                assert_eq!(decoded.height, proposal.height);
+                assert_eq!(decoded.round, proposal.round);
+                assert_eq!(decoded.pol_round, proposal.pol_round);
+                let timestamp = decoded.timestamp.expect("timestamp");
+                assert_eq!(timestamp.seconds, 1_600_000_001);
+                assert_eq!(timestamp.nanos, 99);
+                assert_eq!(decoded.signature, proposal.signature);
+            }
+            other => panic!("expected SignProposal, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_protobuf_rejects_unknown_field_number() {
+        let frame = protobuf_field_frame(99, &[]);
+        let mut r = Cursor::new(frame);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Err(ProtocolError::UnknownMessageType) => {}
+            other => panic!("expected UnknownMessageType, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_protobuf_rejects_non_length_delimited_wire_type() {
+        // Same field number as PingRequest, but tagged as wire type 0
+        // (varint) instead of 2 (length-delimited).
+        let tag = (privval_field::PING_REQUEST << 3) | 0;
+        let mut inner = varint_bytes(tag);
+        inner.extend_from_slice(&varint_bytes(0));
+
+        let mut frame = varint_bytes(inner.len() as u64);
+        frame.extend_from_slice(&inner);
+
+        let mut r = Cursor::new(frame);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Err(ProtocolError::UnknownMessageType) => {}
+            other => panic!("expected UnknownMessageType, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn read_protobuf_rejects_garbage_tag() {
+        // A single continuation byte can never complete a varint.
+        let inner = vec![0x80u8];
+        let mut frame = varint_bytes(inner.len() as u64);
+        frame.extend_from_slice(&inner);
+
+        let mut r = Cursor::new(frame);
+        match Request::read_protobuf(&mut r, DEFAULT_MAX_MSG_LEN) {
+            Err(ProtocolError::MalformedLength) => {}
+            other => panic!("expected MalformedLength, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn pub_key_account_id_round_trips_known_answer() {
+        let pub_key_ed25519 = vec![
+            0x03, 0xa1, 0x07, 0xbf, 0xf3, 0xce, 0x10, 0xbe, 0x1d, 0x70, 0xdd, 0x18, 0xe7, 0x4b,
+            0xc0, 0x99, 0x67, 0xe4, 0xd6, 0x30, 0x9b, 0xa5, 0x0d, 0x5f, 0x1d, 0xdc, 0x86, 0x64,
+            0x12, 0x55, 0x31, 0xb8,
+        ];
+        let response = PubKeyResponse {
+            pub_key_ed25519: pub_key_ed25519.clone(),
+            ..Default::default()
+        };
+
+        let id = pub_key_account_id(&response).expect("valid ed25519 key");
+        assert_eq!(id.to_string(), "56475AA75463474C0285DF5DBF2BCAB73DA65135");
+    }
+}
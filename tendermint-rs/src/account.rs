@@ -1,10 +1,10 @@
 //! Tendermint accounts
 
 use crate::error::Error;
+use k256::ecdsa::VerifyingKey;
 #[cfg(feature = "serde")]
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest, Sha256};
-use signatory::ecdsa::curve::secp256k1;
 use std::{
     fmt::{self, Debug, Display},
     str::FromStr,
@@ -59,8 +59,17 @@ impl Debug for Id {
     }
 }
 
-impl From<secp256k1::PublicKey> for Id {
-    fn from(pk: secp256k1::PublicKey) -> Id {
+impl From<VerifyingKey> for Id {
+    fn from(pk: VerifyingKey) -> Id {
+        let digest = Sha256::digest(pk.to_encoded_point(true).as_bytes());
+        let mut bytes = [0u8; LENGTH];
+        bytes.copy_from_slice(&digest[..LENGTH]);
+        Id(bytes)
+    }
+}
+
+impl From<ed25519_dalek::PublicKey> for Id {
+    fn from(pk: ed25519_dalek::PublicKey) -> Id {
         let digest = Sha256::digest(pk.as_bytes());
         let mut bytes = [0u8; LENGTH];
         bytes.copy_from_slice(&digest[..LENGTH]);
@@ -68,6 +77,13 @@ impl From<secp256k1::PublicKey> for Id {
     }
 }
 
+impl Id {
+    /// Derive an account ID from an Ed25519 validator consensus key
+    pub fn from_ed25519(pk: &ed25519_dalek::PublicKey) -> Id {
+        Id::from(*pk)
+    }
+}
+
 /// Decode account ID from hex
 impl FromStr for Id {
     type Err = Error;
@@ -111,3 +127,38 @@ impl Serialize for Id {
         self.to_string().serialize(serializer)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_secp256k1_matches_known_answer() {
+        let pk = VerifyingKey::from_sec1_bytes(&[
+            0x02, 0x99, 0xc1, 0x26, 0xda, 0x20, 0x39, 0x75, 0x58, 0xf2, 0x36, 0x58, 0x76, 0x4c,
+            0x3a, 0x7c, 0x58, 0x3d, 0xb7, 0xff, 0x70, 0x6e, 0x93, 0x98, 0x1c, 0xc1, 0x70, 0xe2,
+            0x7c, 0xa8, 0x33, 0x62, 0x01,
+        ])
+        .unwrap();
+
+        assert_eq!(
+            Id::from(pk).to_string(),
+            "39768F512E45F5513E360F5868EBFAC9646AA30B"
+        );
+    }
+
+    #[test]
+    fn from_ed25519_matches_known_answer() {
+        let pk = ed25519_dalek::PublicKey::from_bytes(&[
+            0x03, 0xa1, 0x07, 0xbf, 0xf3, 0xce, 0x10, 0xbe, 0x1d, 0x70, 0xdd, 0x18, 0xe7, 0x4b,
+            0xc0, 0x99, 0x67, 0xe4, 0xd6, 0x30, 0x9b, 0xa5, 0x0d, 0x5f, 0x1d, 0xdc, 0x86, 0x64,
+            0x12, 0x55, 0x31, 0xb8,
+        ])
+        .unwrap();
+
+        assert_eq!(
+            Id::from_ed25519(&pk).to_string(),
+            "56475AA75463474C0285DF5DBF2BCAB73DA65135"
+        );
+    }
+}